@@ -11,7 +11,8 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, ExitStatus};
+use thiserror::Error;
 
 // Source: https://www.printables.com/model/27814-filament-swatch
 const SWATCH_SCAD_FILE: &[u8] = include_bytes!("../../templates/swatch.scad");
@@ -80,93 +81,296 @@ impl Default for CustomizerSettings {
     }
 }
 
+/// A single inventory row, keyed by its CSV column headers. Columns beyond
+/// the well-known `material`/`manufacturer` are carried through untouched so
+/// they can be referenced from text/path templates.
 #[derive(Serialize, Deserialize, Debug, Clone)]
-struct FilamentRecord {
-    manufacturer: String,
-    color: String,
-    material: String,
-    temperature: i32,
+#[serde(transparent)]
+pub(crate) struct FilamentRecord(HashMap<String, String>);
+
+impl FilamentRecord {
+    pub(crate) fn field(&self, column: &str) -> Option<&str> {
+        self.0.get(column).map(String::as_str)
+    }
 }
 
 impl Display for FilamentRecord {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut columns: Vec<_> = self.0.iter().collect();
+        columns.sort_by_key(|(column, _)| column.as_str());
+
         write!(
             f,
-            "{} - {} - {}",
-            &self.manufacturer, &self.material, &self.color
+            "{}",
+            columns
+                .into_iter()
+                .map(|(column, value)| format!("{column}={value}"))
+                .collect::<Vec<_>>()
+                .join(", ")
         )
     }
 }
 
-fn render_text_field(filament: &FilamentRecord, field: &OutputField) -> String {
-    match field {
-        OutputField::Manufacturer => filament.manufacturer.to_string(),
-        OutputField::Color => filament.color.to_string(),
-        OutputField::Temperature => format!("0.2mm @ {}Â°C", filament.temperature),
-        OutputField::Material => filament.material.to_string(),
+/// Whether a swatch has already been rendered to `destination`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SwatchStatus {
+    Existing,
+    Pending,
+}
+
+/// Which inventory columns make up a swatch's output path and its display
+/// name (the filename, and what dedup matches existing swatches against).
+#[derive(Serialize, Deserialize, Clone, Debug, clap::Args)]
+pub(crate) struct PathOptions {
+    /// CSV column used as the material component of the output path
+    #[clap(long, default_value = "material")]
+    pub material_column: String,
+    /// CSV column used as the manufacturer component of the output path
+    #[clap(long, default_value = "manufacturer")]
+    pub manufacturer_column: String,
+    /// Template for a swatch's display name - the output filename, and what
+    /// existing swatches are matched against for dedup
+    #[clap(long, default_value = "{manufacturer} - {material} - {color}")]
+    pub display_template: String,
+}
+
+/// Applies a named formatting helper to a template placeholder's value. Kept
+/// around so `{temperature!print_temp}` still renders the historical
+/// "0.2mm @ {temp}°C" label.
+fn apply_helper(helper: &str, value: &str) -> String {
+    match helper {
+        "print_temp" => format!("0.2mm @ {value}°C"),
+        _ => value.to_string(),
+    }
+}
+
+/// Renders a `text_upper`/`text_lower_left`/`text_lower_right`/
+/// `display_template` value against a record. A template with no `{...}`
+/// placeholders is treated as a bare column name; otherwise every
+/// `{column}` (or `{column!helper}`) placeholder is substituted with that
+/// column's value from the record.
+fn render_template(filament: &FilamentRecord, template: &str) -> String {
+    if !template.contains('{') {
+        return filament.field(template).unwrap_or_default().to_string();
+    }
+
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let Some(end) = rest.find('}') else {
+            output.push('{');
+            output.push_str(rest);
+            rest = "";
+            break;
+        };
+
+        let expression = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let (column, helper) = match expression.split_once('!') {
+            Some((column, helper)) => (column, Some(helper)),
+            None => (expression, None),
+        };
+
+        let value = filament.field(column).unwrap_or_default();
+        output.push_str(&match helper {
+            Some(helper) => apply_helper(helper, value),
+            None => value.to_string(),
+        });
     }
+    output.push_str(rest);
+
+    output
+}
+
+/// The `<display>.<ext>` filename a swatch would be rendered to, e.g.
+/// `"Prusa - PLA - Red.stl"` - shared by `output_path` and `inventory_status`
+/// so dedup always compares against the real on-disk name.
+fn output_filename(
+    filament: &FilamentRecord,
+    output_format: &OutputFormat,
+    path_options: &PathOptions,
+) -> PathBuf {
+    let extension = match output_format {
+        OutputFormat::ThreeMf => "3mf",
+        OutputFormat::Stl => "stl",
+        OutputFormat::Png => "png",
+    };
+
+    let display = render_template(filament, &path_options.display_template);
+
+    PathBuf::from(display).with_extension(extension)
+}
+
+/// The hierarchical `material/manufacturer/<display>.<ext>` path a swatch
+/// would be rendered to, without touching the filesystem.
+pub(crate) fn output_path(
+    filament: &FilamentRecord,
+    destination_folder: &Path,
+    output_format: &OutputFormat,
+    path_options: &PathOptions,
+) -> PathBuf {
+    destination_folder
+        .join(
+            filament
+                .field(&path_options.material_column)
+                .unwrap_or_default(),
+        )
+        .join(
+            filament
+                .field(&path_options.manufacturer_column)
+                .unwrap_or_default(),
+        )
+        .join(output_filename(filament, output_format, path_options))
+}
+
+/// Reads the inventory CSV and tags every record with whether it already
+/// exists in `destination_folder`, without invoking OpenSCAD.
+pub(crate) fn inventory_status(
+    inventory: &Path,
+    destination_folder: &Path,
+    output_format: &OutputFormat,
+    path_options: &PathOptions,
+) -> Result<Vec<(FilamentRecord, SwatchStatus)>> {
+    let existing = fs::list_existing_swatches(destination_folder);
+    let mut reader = csv::Reader::from_path(inventory)?;
+
+    Ok(reader
+        .deserialize()
+        .filter_map(Result::ok)
+        .map(|filament: FilamentRecord| {
+            let filename = output_filename(&filament, output_format, path_options)
+                .to_string_lossy()
+                .into_owned();
+            let status = if existing.contains(&filename) {
+                SwatchStatus::Existing
+            } else {
+                SwatchStatus::Pending
+            };
+            (filament, status)
+        })
+        .collect())
+}
+
+/// A swatch that failed to render, carrying enough detail to diagnose it
+/// without re-running OpenSCAD.
+#[derive(Debug, Error)]
+pub(crate) enum RenderError {
+    #[error("openscad exited with {status} while rendering `{filament}`\n  command: {command}\n  stderr: {stderr}")]
+    OpenScad {
+        filament: FilamentRecord,
+        command: String,
+        status: ExitStatus,
+        stderr: String,
+    },
+    #[error("failed to prepare swatch `{filament}`: {source}")]
+    Setup {
+        filament: FilamentRecord,
+        #[source]
+        source: anyhow::Error,
+    },
 }
 
 fn render(
     filament: &FilamentRecord,
     destination_folder: &Path,
     options: &GeneratorOptions,
-) -> Result<()> {
-    let defaults: FilamentSwatchOptions = serde_json::from_slice(SWATCH_PARAMETERS)?;
-    let filename = match options.output_format {
-        OutputFormat::ThreeMf => PathBuf::from(filament.to_string()).with_extension("3mf"),
-        OutputFormat::Stl => PathBuf::from(filament.to_string()).with_extension("stl"),
-    };
+) -> Result<(), RenderError> {
+    let setup = || -> Result<(tempfile::TempDir, PathBuf, PathBuf, PathBuf)> {
+        let defaults: FilamentSwatchOptions = serde_json::from_slice(SWATCH_PARAMETERS)?;
+        let dst = output_path(
+            filament,
+            destination_folder,
+            &options.output_format,
+            &options.swatch_design.path,
+        );
 
-    let dst = destination_folder
-        .join(&filament.material)
-        .join(&filament.manufacturer);
-
-    fs::create_output_dir(&dst)?;
-
-    let dst = dst.join(filename);
-    let work_dir = tempfile::tempdir()?;
-
-    let swatch_options = FilamentSwatchOptions {
-        textstring1: render_text_field(filament, &options.swatch_design.text_upper),
-        textstring2: render_text_field(filament, &options.swatch_design.text_lower_left),
-        textstring3: render_text_field(filament, &options.swatch_design.text_lower_right),
-        texttop_configurable: filament
-            .material
-            .chars()
-            .into_iter()
-            .map(|c| c.to_string())
-            .collect::<Vec<String>>()
-            .join(" "),
-        textsize_lower: options.swatch_design.text_size_lower.to_string(),
-        textsize_upper: options.swatch_design.text_size_upper.to_string(),
-        w: options.swatch_design.width.to_string(),
-        h: options.swatch_design.height.to_string(),
-        ..defaults
-    };
+        fs::create_output_dir(dst.parent().expect("output path always has a parent"))?;
+
+        let work_dir = tempfile::tempdir()?;
 
-    let settings = CustomizerSettings {
-        parameter_sets: hashmap! {
-            "Generator".to_string() => swatch_options
-        },
-        ..Default::default()
+        let swatch_options = FilamentSwatchOptions {
+            textstring1: render_template(filament, &options.swatch_design.text_upper),
+            textstring2: render_template(filament, &options.swatch_design.text_lower_left),
+            textstring3: render_template(filament, &options.swatch_design.text_lower_right),
+            texttop_configurable: filament
+                .field(&options.swatch_design.path.material_column)
+                .unwrap_or_default()
+                .chars()
+                .map(|c| c.to_string())
+                .collect::<Vec<String>>()
+                .join(" "),
+            textsize_lower: options.swatch_design.text_size_lower.to_string(),
+            textsize_upper: options.swatch_design.text_size_upper.to_string(),
+            w: options.swatch_design.width.to_string(),
+            h: options.swatch_design.height.to_string(),
+            ..defaults
+        };
+
+        let settings = CustomizerSettings {
+            parameter_sets: hashmap! {
+                "Generator".to_string() => swatch_options
+            },
+            ..Default::default()
+        };
+
+        let swatch_path = work_dir.path().join("swatch.scad");
+        std::fs::write(&swatch_path, SWATCH_SCAD_FILE)?;
+
+        let swatch_parameters = work_dir.path().join("settings.json");
+        serde_json::to_writer_pretty(&File::create(&swatch_parameters)?, &settings)?;
+
+        Ok((work_dir, dst, swatch_path, swatch_parameters))
     };
 
-    let swatch_path = work_dir.path().join("swatch.scad");
-    std::fs::write(&swatch_path, SWATCH_SCAD_FILE)?;
+    let (_work_dir, dst, swatch_path, swatch_parameters) =
+        setup().map_err(|source| RenderError::Setup {
+            filament: filament.clone(),
+            source,
+        })?;
 
-    let swatch_parameters = work_dir.path().join("settings.json");
-    serde_json::to_writer_pretty(&File::create(&swatch_parameters)?, &settings)?;
+    let mut command = Command::new(&options.openscad_path);
+    command.arg("-o").arg(&dst);
 
-    Command::new(&options.openscad_path)
-        .arg("-o")
-        .arg(dst)
+    if matches!(options.output_format, OutputFormat::Png) {
+        command
+            .arg(format!(
+                "--imgsize={},{}",
+                options.swatch_design.image_width, options.swatch_design.image_height
+            ))
+            .arg(format!(
+                "--colorscheme={}",
+                options.swatch_design.colorscheme
+            ))
+            .arg("--render");
+    }
+
+    command
         .arg("-p")
-        .arg(swatch_parameters)
+        .arg(&swatch_parameters)
         .arg("-P")
         .arg("Generator")
-        .arg(swatch_path)
-        .output()?;
+        .arg(&swatch_path);
+
+    let invocation = format!("{command:?}");
+    let output = command.output().map_err(|source| RenderError::Setup {
+        filament: filament.clone(),
+        source: source.into(),
+    })?;
+
+    if !output.status.success() {
+        return Err(RenderError::OpenScad {
+            filament: filament.clone(),
+            command: invocation,
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
 
     Ok(())
 }
@@ -178,54 +382,96 @@ pub(crate) fn write(options: &GeneratorOptions) -> Result<()> {
         .unwrap_or_else(|| PathBuf::from("."));
 
     fs::create_output_dir(&destination_folder)?;
-    let existing = fs::list_existing_swatches(&destination_folder);
 
-    let mut reader = csv::Reader::from_path(
-        options
-            .inventory
-            .clone()
-            .unwrap_or_else(|| PathBuf::from("inventory.txt")),
-    )?;
+    let inventory_path = options
+        .inventory
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("inventory.txt"));
 
-    let filaments: Vec<_> = reader
-        .deserialize()
-        .filter_map(Result::ok)
-        .filter(|f: &FilamentRecord| !existing.contains(&f.to_string()))
-        .collect();
+    let filaments: Vec<_> = inventory_status(
+        &inventory_path,
+        &destination_folder,
+        &options.output_format,
+        &options.swatch_design.path,
+    )?
+    .into_iter()
+    .filter(|(_, status)| *status == SwatchStatus::Pending)
+    .map(|(filament, _)| filament)
+    .collect();
 
     let progress_bar_style =
         ProgressStyle::default_bar().template("[{elapsed_precise}] {bar:40} {pos:>7}/{len:7}")?;
 
-    filaments
+    if options.fail_fast {
+        filaments
+            .par_iter()
+            .progress_with_style(progress_bar_style)
+            .try_for_each(|filament| render(filament, &destination_folder, options))?;
+        println!("{} swatch(es) rendered", filaments.len());
+        return Ok(());
+    }
+
+    let results: Vec<Result<(), RenderError>> = filaments
         .par_iter()
         .progress_with_style(progress_bar_style)
-        .try_for_each(|filament| render(filament, &destination_folder, options))?;
-    Ok(())
+        .map(|filament| render(filament, &destination_folder, options))
+        .collect();
+
+    report_render_results(results)
 }
 
-#[derive(clap::ValueEnum, Serialize, Deserialize, Clone, Debug)]
-pub(crate) enum OutputField {
-    Manufacturer,
-    Color,
-    Temperature,
-    Material,
+/// Prints the per-run summary and surfaces any failures as an error -
+/// `--fail-fast` only controls *when* rendering aborts, not *whether* a
+/// failed run ever exits non-zero.
+fn report_render_results(results: Vec<Result<(), RenderError>>) -> Result<()> {
+    let failures: Vec<&RenderError> = results
+        .iter()
+        .filter_map(|result| result.as_ref().err())
+        .collect();
+
+    if !failures.is_empty() {
+        eprintln!("\nFailed to render {} swatch(es):\n", failures.len());
+        for failure in &failures {
+            eprintln!("{failure}\n");
+        }
+    }
+
+    println!(
+        "{} succeeded, {} failed",
+        results.len() - failures.len(),
+        failures.len()
+    );
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "{} of {} swatch(es) failed to render",
+            failures.len(),
+            results.len()
+        )
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
 pub(crate) enum OutputFormat {
+    #[serde(rename = "3mf")]
     ThreeMf,
     Stl,
+    Png,
 }
 
 impl ValueEnum for OutputFormat {
     fn value_variants<'a>() -> &'a [Self] {
-        &[Self::Stl, Self::ThreeMf]
+        &[Self::Stl, Self::ThreeMf, Self::Png]
     }
 
     fn to_possible_value(&self) -> Option<PossibleValue> {
         Some(match self {
             Self::Stl => PossibleValue::new("stl").help("Generate .stl files"),
             Self::ThreeMf => PossibleValue::new("3mf").help("Generate .3mf files"),
+            Self::Png => PossibleValue::new("png").help("Generate .png preview images"),
         })
     }
 }
@@ -238,19 +484,54 @@ pub(crate) struct SwatchOptions {
     /// Height of the filament swatch
     #[clap(long, default_value_t = 32.0)]
     pub height: f32,
-    /// Upper text line
-    #[clap(long, default_value = "temperature")]
-    pub text_upper: OutputField,
-    /// Left lower text line
-    #[clap(long, default_value = "manufacturer")]
-    pub text_lower_left: OutputField,
-    /// Left lower text line
-    #[clap(long, default_value = "color")]
-    pub text_lower_right: OutputField,
+    /// Upper text line: a bare column name, or a `{column}`/`{column!helper}`
+    /// template string, e.g. `"{material} {diameter}mm"`
+    #[clap(long, default_value = "{temperature!print_temp}")]
+    pub text_upper: String,
+    /// Left lower text line, same syntax as `text_upper`
+    #[clap(long, default_value = "{manufacturer}")]
+    pub text_lower_left: String,
+    /// Right lower text line, same syntax as `text_upper`
+    #[clap(long, default_value = "{color}")]
+    pub text_lower_right: String,
     /// Upper text size in mm
     #[clap(long, default_value_t = 4)]
     pub text_size_upper: i8,
     /// Lower text size in mm
     #[clap(long, default_value_t = 5)]
     pub text_size_lower: i8,
+    /// Width of the PNG preview image, in pixels
+    #[clap(long, default_value_t = 800)]
+    pub image_width: u32,
+    /// Height of the PNG preview image, in pixels
+    #[clap(long, default_value_t = 600)]
+    pub image_height: u32,
+    /// OpenSCAD colorscheme used when rendering PNG previews
+    #[clap(long, default_value = "Tomorrow")]
+    pub colorscheme: String,
+    #[clap(flatten)]
+    pub path: PathOptions,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_render_results_ok_when_no_failures() {
+        let results: Vec<Result<(), RenderError>> = vec![Ok(()), Ok(())];
+        assert!(report_render_results(results).is_ok());
+    }
+
+    #[test]
+    fn report_render_results_errors_without_fail_fast() {
+        let results: Vec<Result<(), RenderError>> = vec![
+            Ok(()),
+            Err(RenderError::Setup {
+                filament: FilamentRecord(HashMap::new()),
+                source: anyhow::anyhow!("boom"),
+            }),
+        ];
+        assert!(report_render_results(results).is_err());
+    }
 }
@@ -0,0 +1,4 @@
+pub(crate) mod generate;
+pub(crate) mod query;
+#[cfg(feature = "serve")]
+pub(crate) mod serve;
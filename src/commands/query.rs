@@ -0,0 +1,175 @@
+use crate::commands::generate::{self, FilamentRecord, OutputFormat, PathOptions, SwatchStatus};
+use anyhow::Result;
+use clap::ValueHint;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub(crate) enum GroupBy {
+    Material,
+    Manufacturer,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub(crate) enum QueryFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct QueryOptions {
+    /// Inventory CSV
+    #[clap(short, long, value_hint=ValueHint::FilePath)]
+    pub(crate) inventory: Option<PathBuf>,
+    /// Output directory
+    #[clap(short, long, value_hint=ValueHint::DirPath)]
+    pub(crate) destination: Option<PathBuf>,
+    /// Output format the computed output path should assume
+    #[clap(long, default_value = "stl")]
+    pub(crate) output_format: OutputFormat,
+    /// Only include records for this material
+    #[clap(long)]
+    pub(crate) material: Option<String>,
+    /// Only include records for this manufacturer
+    #[clap(long)]
+    pub(crate) manufacturer: Option<String>,
+    /// Group the results by this field
+    #[clap(long)]
+    pub(crate) group_by: Option<GroupBy>,
+    /// Output format
+    #[clap(long, default_value = "table")]
+    pub(crate) format: QueryFormat,
+    #[clap(flatten)]
+    pub(crate) path: PathOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct QueryRecord {
+    #[serde(flatten)]
+    filament: FilamentRecord,
+    path: PathBuf,
+    status: SwatchStatus,
+}
+
+impl QueryRecord {
+    fn group_key(&self, group_by: &GroupBy, path_options: &PathOptions) -> String {
+        match group_by {
+            GroupBy::Material => self.filament.field(&path_options.material_column),
+            GroupBy::Manufacturer => self.filament.field(&path_options.manufacturer_column),
+        }
+        .unwrap_or_default()
+        .to_string()
+    }
+}
+
+fn matches_filters(record: &QueryRecord, options: &QueryOptions) -> bool {
+    options.material.as_deref().is_none_or(|material| {
+        record
+            .filament
+            .field(&options.path.material_column)
+            .is_some_and(|value| value.eq_ignore_ascii_case(material))
+    }) && options.manufacturer.as_deref().is_none_or(|manufacturer| {
+        record
+            .filament
+            .field(&options.path.manufacturer_column)
+            .is_some_and(|value| value.eq_ignore_ascii_case(manufacturer))
+    })
+}
+
+fn group<'a>(
+    records: &'a [QueryRecord],
+    group_by: &GroupBy,
+    path_options: &PathOptions,
+) -> BTreeMap<String, Vec<&'a QueryRecord>> {
+    let mut groups: BTreeMap<String, Vec<&QueryRecord>> = BTreeMap::new();
+    for record in records {
+        groups
+            .entry(record.group_key(group_by, path_options))
+            .or_default()
+            .push(record);
+    }
+    groups
+}
+
+fn print_table(records: &[&QueryRecord], path_options: &PathOptions) {
+    println!(
+        "{:<20} {:<20} {:<9}  PATH",
+        "MATERIAL", "MANUFACTURER", "STATUS"
+    );
+    for record in records {
+        println!(
+            "{:<20} {:<20} {:<9}  {}",
+            record
+                .filament
+                .field(&path_options.material_column)
+                .unwrap_or_default(),
+            record
+                .filament
+                .field(&path_options.manufacturer_column)
+                .unwrap_or_default(),
+            match record.status {
+                SwatchStatus::Existing => "existing",
+                SwatchStatus::Pending => "pending",
+            },
+            record.path.display(),
+        );
+    }
+}
+
+pub(crate) fn run(options: &QueryOptions) -> Result<()> {
+    let destination_folder = options
+        .destination
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."));
+    let inventory_path = options
+        .inventory
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("inventory.txt"));
+
+    let records: Vec<QueryRecord> = generate::inventory_status(
+        &inventory_path,
+        &destination_folder,
+        &options.output_format,
+        &options.path,
+    )?
+    .into_iter()
+    .map(|(filament, status)| {
+        let path = generate::output_path(
+            &filament,
+            &destination_folder,
+            &options.output_format,
+            &options.path,
+        );
+        QueryRecord {
+            filament,
+            path,
+            status,
+        }
+    })
+    .filter(|record| matches_filters(record, options))
+    .collect();
+
+    match (&options.format, &options.group_by) {
+        (QueryFormat::Json, Some(group_by)) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&group(&records, group_by, &options.path))?
+            );
+        }
+        (QueryFormat::Json, None) => {
+            println!("{}", serde_json::to_string_pretty(&records)?);
+        }
+        (QueryFormat::Table, Some(group_by)) => {
+            for (key, group_records) in group(&records, group_by, &options.path) {
+                println!("== {key} ==");
+                print_table(&group_records, &options.path);
+            }
+        }
+        (QueryFormat::Table, None) => {
+            print_table(&records.iter().collect::<Vec<_>>(), &options.path);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use crate::commands::generate::{self, FilamentRecord, OutputFormat, PathOptions, SwatchStatus};
+use anyhow::Result;
+use clap::ValueHint;
+use serde::Serialize;
+use std::fs::File;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tiny_http::{Header, Response, Server};
+
+const GALLERY_HTML: &str = include_str!("../../templates/gallery.html");
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct ServeOptions {
+    /// Inventory CSV used to label swatches in the gallery
+    #[clap(short, long, value_hint=ValueHint::FilePath)]
+    pub(crate) inventory: Option<PathBuf>,
+    /// Output directory to browse
+    #[clap(short, long, value_hint=ValueHint::DirPath)]
+    pub(crate) destination: Option<PathBuf>,
+    /// Output format used to compute swatch paths
+    #[clap(long, default_value = "stl")]
+    pub(crate) output_format: OutputFormat,
+    /// Address to bind the gallery server to
+    #[clap(long, default_value = "127.0.0.1:8080")]
+    pub(crate) bind: SocketAddr,
+    #[clap(flatten)]
+    pub(crate) path: PathOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct GallerySwatch {
+    #[serde(flatten)]
+    filament: FilamentRecord,
+    path: String,
+    status: SwatchStatus,
+}
+
+fn inventory_json(
+    inventory: &Path,
+    destination: &Path,
+    output_format: &OutputFormat,
+    path_options: &PathOptions,
+) -> Result<String> {
+    let swatches: Vec<GallerySwatch> =
+        generate::inventory_status(inventory, destination, output_format, path_options)?
+            .into_iter()
+            .map(|(filament, status)| {
+                let full_path =
+                    generate::output_path(&filament, destination, output_format, path_options);
+                let path = full_path
+                    .strip_prefix(destination)
+                    .unwrap_or(&full_path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                GallerySwatch {
+                    filament,
+                    path,
+                    status,
+                }
+            })
+            .collect();
+
+    Ok(serde_json::to_string(&swatches)?)
+}
+
+/// Resolves `relative` against `destination_folder`, rejecting anything that
+/// would escape it (`..` segments, a leading `/` left over after the
+/// `/swatches/` prefix is stripped, or a symlink pointing outside) so the gallery can only ever read
+/// files under `destination_folder`.
+fn resolve_swatch_path(destination_folder: &Path, relative: &str) -> Option<PathBuf> {
+    let root = destination_folder.canonicalize().ok()?;
+    let candidate = root.join(relative.trim_start_matches('/'));
+    let candidate = candidate.canonicalize().ok()?;
+
+    candidate.starts_with(&root).then_some(candidate)
+}
+
+fn serve_file(request: tiny_http::Request, path: Option<PathBuf>) {
+    let response = match path.and_then(|path| File::open(path).ok()) {
+        Some(file) => request.respond(Response::from_file(file)),
+        None => request.respond(Response::from_string("not found").with_status_code(404)),
+    };
+
+    if let Err(e) = response {
+        eprintln!("failed to send gallery response: {e}");
+    }
+}
+
+/// Starts a read-only HTTP server browsing `destination`, grouped by
+/// material/manufacturer, backed by the same inventory/status data as
+/// `query`. Never shells out to OpenSCAD.
+pub(crate) fn run(options: &ServeOptions) -> Result<()> {
+    let destination_folder = options
+        .destination
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."));
+    let inventory_path = options
+        .inventory
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("inventory.txt"));
+
+    let server = Server::http(options.bind)
+        .map_err(|e| anyhow::anyhow!("failed to bind gallery server to {}: {e}", options.bind))?;
+
+    println!(
+        "Serving swatch gallery for `{}` on http://{}",
+        destination_folder.display(),
+        options.bind
+    );
+
+    for request in server.incoming_requests() {
+        let url = request.url().to_string();
+
+        if url == "/api/swatches" {
+            match inventory_json(
+                &inventory_path,
+                &destination_folder,
+                &options.output_format,
+                &options.path,
+            ) {
+                Ok(body) => {
+                    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                        .expect("static header is always valid");
+                    let _ = request.respond(Response::from_string(body).with_header(header));
+                }
+                Err(e) => {
+                    let _ =
+                        request.respond(Response::from_string(e.to_string()).with_status_code(500));
+                }
+            }
+            continue;
+        }
+
+        if let Some(relative) = url.strip_prefix("/swatches/") {
+            let path = resolve_swatch_path(&destination_folder, relative);
+            serve_file(request, path);
+            continue;
+        }
+
+        let header = Header::from_bytes(&b"Content-Type"[..], &b"text/html; charset=utf-8"[..])
+            .expect("static header is always valid");
+        let _ = request.respond(Response::from_string(GALLERY_HTML).with_header(header));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use crate::commands::generate::OutputFormat;
+use crate::helpers::fs::{self, PathError};
+use crate::GeneratorOptions;
+use clap::parser::ValueSource;
+use clap::ArgMatches;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+const CONFIG_FILE_NAMES: [&str; 2] = ["swatchify.yaml", "swatchify.toml"];
+
+#[derive(Debug, Error)]
+pub(crate) enum ConfigError {
+    #[error("Could not read config file `{}`", .1.display())]
+    Read(#[source] std::io::Error, PathBuf),
+    #[error("Could not parse YAML config file `{}`", .1.display())]
+    Yaml(#[source] serde_yaml::Error, PathBuf),
+    #[error("Could not parse TOML config file `{}`", .1.display())]
+    Toml(#[source] toml::de::Error, PathBuf),
+    #[error("Config file `{}` has an unsupported extension, expected .yaml, .yml or .toml", .0.display())]
+    UnsupportedFormat(PathBuf),
+    #[error(transparent)]
+    Path(#[from] PathError),
+}
+
+/// Mirrors [`GeneratorOptions`]/[`SwatchOptions`], but every field is optional
+/// so a config file only needs to set the values it wants to override.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub(crate) struct FileConfig {
+    pub(crate) inventory: Option<PathBuf>,
+    pub(crate) destination: Option<PathBuf>,
+    pub(crate) output_format: Option<OutputFormat>,
+    pub(crate) openscad_path: Option<PathBuf>,
+    pub(crate) force: Option<bool>,
+    pub(crate) fail_fast: Option<bool>,
+    pub(crate) width: Option<f32>,
+    pub(crate) height: Option<f32>,
+    pub(crate) text_upper: Option<String>,
+    pub(crate) text_lower_left: Option<String>,
+    pub(crate) text_lower_right: Option<String>,
+    pub(crate) text_size_upper: Option<i8>,
+    pub(crate) text_size_lower: Option<i8>,
+    pub(crate) image_width: Option<u32>,
+    pub(crate) image_height: Option<u32>,
+    pub(crate) colorscheme: Option<String>,
+    pub(crate) material_column: Option<String>,
+    pub(crate) manufacturer_column: Option<String>,
+    pub(crate) display_template: Option<String>,
+}
+
+/// Looks for a config file in the current directory, then in the
+/// platform-specific config directory (e.g. `~/.config/swatchify/` on Linux).
+fn discover_path() -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+        .or_else(|| {
+            let project_dirs = directories::ProjectDirs::from("", "", "swatchify")?;
+            CONFIG_FILE_NAMES
+                .iter()
+                .map(|name| project_dirs.config_dir().join(name))
+                .find(|path| path.is_file())
+        })
+}
+
+fn parse(path: &Path) -> Result<FileConfig, ConfigError> {
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| ConfigError::Read(e, path.to_path_buf()))?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml") | Some("yml") => {
+            serde_yaml::from_str(&contents).map_err(|e| ConfigError::Yaml(e, path.to_path_buf()))
+        }
+        Some("toml") => {
+            toml::from_str(&contents).map_err(|e| ConfigError::Toml(e, path.to_path_buf()))
+        }
+        _ => Err(ConfigError::UnsupportedFormat(path.to_path_buf())),
+    }
+}
+
+/// Loads the config file at `explicit_path`, or the first discovered default.
+/// Does not validate any paths it sets - `apply` only validates the fields
+/// that survive CLI-override precedence, since a config value that's about
+/// to be overridden never needs to resolve.
+fn load(explicit_path: Option<&Path>) -> Result<Option<FileConfig>, ConfigError> {
+    let path = match explicit_path {
+        Some(path) => {
+            fs::validate_file_path(path)?;
+            Some(path.to_path_buf())
+        }
+        None => discover_path(),
+    };
+
+    let Some(path) = path else {
+        return Ok(None);
+    };
+
+    parse(&path).map(Some)
+}
+
+/// Merges a loaded config file into `options`, only overwriting fields the
+/// user did not pass explicitly on the command line. Precedence is therefore
+/// built-in defaults < config file < CLI flags.
+pub(crate) fn apply(
+    options: &mut GeneratorOptions,
+    matches: &ArgMatches,
+) -> Result<(), ConfigError> {
+    let Some(config) = load(options.config.as_deref())? else {
+        return Ok(());
+    };
+
+    let from_cli = |id: &str| matches!(matches.value_source(id), Some(ValueSource::CommandLine));
+
+    if !from_cli("inventory") {
+        if let Some(inventory) = config.inventory {
+            fs::validate_file_path(&inventory)?;
+            options.inventory = Some(inventory);
+        }
+    }
+    if !from_cli("destination") {
+        if let Some(destination) = config.destination {
+            fs::validate_folder_path(&destination)?;
+            options.destination = Some(destination);
+        }
+    }
+    if !from_cli("output_format") {
+        if let Some(output_format) = config.output_format {
+            options.output_format = output_format;
+        }
+    }
+    if !from_cli("openscad_path") {
+        if let Some(openscad_path) = config.openscad_path {
+            options.openscad_path = openscad_path;
+        }
+    }
+    if !from_cli("force") {
+        if let Some(force) = config.force {
+            options.force = force;
+        }
+    }
+    if !from_cli("fail_fast") {
+        if let Some(fail_fast) = config.fail_fast {
+            options.fail_fast = fail_fast;
+        }
+    }
+    if !from_cli("width") {
+        if let Some(width) = config.width {
+            options.swatch_design.width = width;
+        }
+    }
+    if !from_cli("height") {
+        if let Some(height) = config.height {
+            options.swatch_design.height = height;
+        }
+    }
+    if !from_cli("text_upper") {
+        if let Some(text_upper) = config.text_upper {
+            options.swatch_design.text_upper = text_upper;
+        }
+    }
+    if !from_cli("text_lower_left") {
+        if let Some(text_lower_left) = config.text_lower_left {
+            options.swatch_design.text_lower_left = text_lower_left;
+        }
+    }
+    if !from_cli("text_lower_right") {
+        if let Some(text_lower_right) = config.text_lower_right {
+            options.swatch_design.text_lower_right = text_lower_right;
+        }
+    }
+    if !from_cli("text_size_upper") {
+        if let Some(text_size_upper) = config.text_size_upper {
+            options.swatch_design.text_size_upper = text_size_upper;
+        }
+    }
+    if !from_cli("text_size_lower") {
+        if let Some(text_size_lower) = config.text_size_lower {
+            options.swatch_design.text_size_lower = text_size_lower;
+        }
+    }
+    if !from_cli("image_width") {
+        if let Some(image_width) = config.image_width {
+            options.swatch_design.image_width = image_width;
+        }
+    }
+    if !from_cli("image_height") {
+        if let Some(image_height) = config.image_height {
+            options.swatch_design.image_height = image_height;
+        }
+    }
+    if !from_cli("colorscheme") {
+        if let Some(colorscheme) = config.colorscheme {
+            options.swatch_design.colorscheme = colorscheme;
+        }
+    }
+    if !from_cli("material_column") {
+        if let Some(material_column) = config.material_column {
+            options.swatch_design.path.material_column = material_column;
+        }
+    }
+    if !from_cli("manufacturer_column") {
+        if let Some(manufacturer_column) = config.manufacturer_column {
+            options.swatch_design.path.manufacturer_column = manufacturer_column;
+        }
+    }
+    if !from_cli("display_template") {
+        if let Some(display_template) = config.display_template {
+            options.swatch_design.path.display_template = display_template;
+        }
+    }
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use std::path::Path;
 use thiserror::Error;
 use walkdir::WalkDir;
 
-const PRINTABLE_FILE_TYPES: [&str; 4] = ["step", "3mf", "stl", "obj"];
+const PRINTABLE_FILE_TYPES: [&str; 5] = ["step", "3mf", "stl", "obj", "png"];
 
 #[derive(Debug, Error)]
 pub(crate) enum PathError {
@@ -57,3 +57,58 @@ pub(crate) fn create_output_dir(path: &Path) -> anyhow::Result<(), PathError> {
         Err(_e) => Ok(std::fs::create_dir_all(path)?),
     }
 }
+
+/// Validates that `path` either already is a directory, or can become one
+/// (i.e. its parent exists), without creating anything on disk.
+pub(crate) fn validate_folder_path(path: &Path) -> anyhow::Result<(), PathError> {
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => Ok(()),
+        Ok(_) => Err(PathError::Inaccessible(path.to_string_lossy().to_string())),
+        Err(_) if parent_is_dir(path) => Ok(()),
+        Err(e) => Err(PathError::Canonicalize(e)),
+    }
+}
+
+/// `Path::parent` of a single-component relative path (e.g. `"output"`)
+/// returns `Some("")`, and `Path::new("").is_dir()` is always `false` - so
+/// an empty parent is treated as the current directory instead.
+fn parent_is_dir(path: &Path) -> bool {
+    match path.parent() {
+        Some(parent) if parent.as_os_str().is_empty() => Path::new(".").is_dir(),
+        Some(parent) => parent.is_dir(),
+        None => false,
+    }
+}
+
+/// Validates that `path` points at an existing, readable file.
+pub(crate) fn validate_file_path(path: &Path) -> anyhow::Result<(), PathError> {
+    let metadata = std::fs::metadata(path).map_err(PathError::Canonicalize)?;
+    if metadata.is_file() {
+        Ok(())
+    } else {
+        Err(PathError::Inaccessible(path.to_string_lossy().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_folder_path_accepts_relative_dir_not_yet_created() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = validate_folder_path(Path::new("output"));
+
+        std::env::set_current_dir(original_dir).unwrap();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn validate_folder_path_rejects_dir_with_missing_parent() {
+        let result = validate_folder_path(Path::new("no/such/parent/output"));
+        assert!(result.is_err());
+    }
+}
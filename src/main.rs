@@ -1,6 +1,9 @@
 use anyhow::Result;
-use clap::{ArgAction, ColorChoice, Parser, ValueHint};
+use clap::{ArgAction, ColorChoice, CommandFactory, FromArgMatches, ValueHint};
 use commands::generate::{OutputFormat, SwatchOptions};
+use commands::query::QueryOptions;
+#[cfg(feature = "serve")]
+use commands::serve::ServeOptions;
 use std::path::PathBuf;
 
 mod commands;
@@ -22,6 +25,11 @@ pub(crate) struct Cli {
 #[derive(clap::Subcommand, Debug)]
 pub(crate) enum Commands {
     Generate(GeneratorOptions),
+    /// Inspect the inventory and output directory without rendering anything
+    Query(QueryOptions),
+    /// Browse generated swatches in a local web gallery
+    #[cfg(feature = "serve")]
+    Serve(ServeOptions),
 }
 
 #[derive(Debug, clap::Args)]
@@ -29,29 +37,48 @@ pub(crate) enum Commands {
 pub(crate) struct GeneratorOptions {
     /// Inventory CSV
     #[clap(short, long, value_hint=ValueHint::FilePath)]
-    inventory: Option<PathBuf>,
+    pub(crate) inventory: Option<PathBuf>,
     /// Output directory
     #[clap(short, long, value_hint=ValueHint::DirPath)]
-    destination: Option<PathBuf>,
+    pub(crate) destination: Option<PathBuf>,
     /// Output format
     #[clap(long, default_value = "stl")]
-    output_format: OutputFormat,
-    /// Output format
+    pub(crate) output_format: OutputFormat,
+    /// Path to the OpenSCAD binary
     #[clap(long, value_hint=ValueHint::FilePath, default_value = commands::generate::OPEN_SCAD_PATH)]
-    openscad_path: PathBuf,
+    pub(crate) openscad_path: PathBuf,
     /// Force export and regenerate all existing files
     #[clap(short, long)]
     pub(crate) force: bool,
-    /// Testing
+    /// Abort on the first render failure instead of collecting a summary
+    #[clap(long)]
+    pub(crate) fail_fast: bool,
+    /// Config file (YAML or TOML) providing defaults for the other options.
+    /// Defaults to `swatchify.yaml`/`swatchify.toml` in the current
+    /// directory, then the platform config directory.
+    #[clap(short, long, value_hint=ValueHint::FilePath)]
+    pub(crate) config: Option<PathBuf>,
     #[clap(flatten)]
-    swatch_design: SwatchOptions,
+    pub(crate) swatch_design: SwatchOptions,
 }
 
 fn main() -> Result<()> {
-    let args = Cli::parse();
+    let mut command = Cli::command();
+    let matches = command.get_matches_mut();
+    let mut args = Cli::from_arg_matches(&matches)?;
+
+    if let Commands::Generate(options) = &mut args.command {
+        let generate_matches = matches
+            .subcommand_matches("generate")
+            .expect("clap guarantees matches for the active subcommand");
+        helpers::config::apply(options, generate_matches)?;
+    }
 
     match &args.command {
         Commands::Generate(options) => commands::generate::write(options)?,
+        Commands::Query(options) => commands::query::run(options)?,
+        #[cfg(feature = "serve")]
+        Commands::Serve(options) => commands::serve::run(options)?,
     }
     Ok(())
 }